@@ -1,4 +1,4 @@
-use crossterm::event::{self, Event, KeyCode};
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyModifiers};
 use crossterm::terminal::{
     disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen,
 };
@@ -8,23 +8,53 @@ use crossterm::{
 };
 use ratatui::{
     backend::CrosstermBackend,
+    buffer::Buffer,
     layout::{Constraint, Direction, Layout, Rect},
-    style::{Color, Style},
-    widgets::Paragraph,
+    style::{Color, Modifier, Style},
+    widgets::{Paragraph, StatefulWidget, Widget},
     Frame, Terminal,
 };
 use std::io;
-
-struct DrawingWidget {
+use std::str::FromStr;
+
+// How many grid snapshots the undo/redo stacks keep around before the
+// oldest entries are dropped.
+const MAX_HISTORY: usize = 50;
+
+// Fixed on-screen size of a single logical pixel, in terminal cells. The
+// viewport shows however many of these fit in the render area, rather than
+// squeezing the whole logical canvas into it.
+const CELL_PIXEL_WIDTH: u16 = 2;
+const CELL_PIXEL_HEIGHT: u16 = 1;
+
+// Bounds and step for growing/shrinking the logical canvas at runtime.
+const MIN_CANVAS_DIM: usize = 4;
+const MAX_CANVAS_DIM: usize = 256;
+const CANVAS_RESIZE_STEP: usize = 8;
+
+// The mutable drawing state, owned by `App` and passed into `DrawingWidget`
+// via `render_stateful_widget` (the widget itself carries no data).
+struct DrawingState {
     grid: Vec<Vec<Color>>,
     cursor: (usize, usize),
+    selection_anchor: Option<(usize, usize)>,
+    selection: Option<((usize, usize), (usize, usize))>,
+    undo_stack: Vec<Vec<Vec<Color>>>,
+    redo_stack: Vec<Vec<Vec<Color>>>,
+    // Top-left logical cell currently shown in the viewport.
+    scroll: (usize, usize),
 }
 
-impl DrawingWidget {
-    fn new(width: usize, height: usize) -> DrawingWidget {
-        DrawingWidget {
+impl DrawingState {
+    fn new(width: usize, height: usize) -> DrawingState {
+        DrawingState {
             grid: vec![vec![Color::Reset; width]; height],
             cursor: (0, 0),
+            selection_anchor: None,
+            selection: None,
+            undo_stack: Vec::new(),
+            redo_stack: Vec::new(),
+            scroll: (0, 0),
         }
     }
 
@@ -40,33 +70,221 @@ impl DrawingWidget {
         }
     }
 
+    // Grows or shrinks the logical canvas by `delta` cells in both
+    // dimensions, clamped to [MIN_CANVAS_DIM, MAX_CANVAS_DIM]. New cells are
+    // `Color::Reset`. The old size is pushed onto the undo stack like any
+    // other edit, so undo/redo always restores a grid whose dimensions match
+    // what was current at that point.
+    fn resize_canvas(&mut self, delta: isize) {
+        self.push_undo();
+
+        let width = self.grid[0].len();
+        let height = self.grid.len();
+        let new_width =
+            (width as isize + delta).clamp(MIN_CANVAS_DIM as isize, MAX_CANVAS_DIM as isize) as usize;
+        let new_height =
+            (height as isize + delta).clamp(MIN_CANVAS_DIM as isize, MAX_CANVAS_DIM as isize) as usize;
+
+        for row in self.grid.iter_mut() {
+            row.resize(new_width, Color::Reset);
+        }
+        self.grid.resize(new_height, vec![Color::Reset; new_width]);
+
+        self.clamp_to_grid();
+    }
+
+    // Pulls `cursor` and `scroll` back inside the current grid's bounds, and
+    // drops `selection`/`selection_anchor` if either no longer fits. Needed
+    // after swapping in a snapshot of a different size (undo/redo across a
+    // canvas resize) as well as after `resize_canvas` itself. This is the
+    // single place that re-validates grid coordinates after a resize, so
+    // `fill_selection`/`clear_selection` can trust `self.selection` is always
+    // in-bounds rather than re-deriving their own bounds checks.
+    fn clamp_to_grid(&mut self) {
+        let width = self.grid[0].len();
+        let height = self.grid.len();
+        self.cursor.0 = self.cursor.0.min(width - 1);
+        self.cursor.1 = self.cursor.1.min(height - 1);
+        self.scroll.0 = self.scroll.0.min(width - 1);
+        self.scroll.1 = self.scroll.1.min(height - 1);
+
+        let in_bounds = |(x, y): (usize, usize)| x < width && y < height;
+        if self.selection_anchor.is_some_and(|a| !in_bounds(a)) {
+            self.selection_anchor = None;
+        }
+        if self
+            .selection
+            .is_some_and(|(min, max)| !in_bounds(min) || !in_bounds(max))
+        {
+            self.selection = None;
+        }
+    }
+
+    // Keeps the last scroll offset until the cursor would fall outside the
+    // visible window, then nudges it back just far enough to contain it.
+    fn update_scroll(&mut self, visible_cols: usize, visible_rows: usize) {
+        let canvas_width = self.grid[0].len();
+        let canvas_height = self.grid.len();
+        let (cursor_x, cursor_y) = self.cursor;
+        let (mut off_x, mut off_y) = self.scroll;
+
+        if cursor_x < off_x {
+            off_x = cursor_x;
+        } else if cursor_x >= off_x + visible_cols {
+            off_x = cursor_x + 1 - visible_cols;
+        }
+        if cursor_y < off_y {
+            off_y = cursor_y;
+        } else if cursor_y >= off_y + visible_rows {
+            off_y = cursor_y + 1 - visible_rows;
+        }
+
+        self.scroll = (
+            off_x.min(canvas_width.saturating_sub(visible_cols)),
+            off_y.min(canvas_height.saturating_sub(visible_rows)),
+        );
+    }
+
+    // Snapshots the grid onto the undo stack and drops the redo stack, since
+    // it no longer follows from what's about to be edited.
+    fn push_undo(&mut self) {
+        self.undo_stack.push(self.grid.clone());
+        if self.undo_stack.len() > MAX_HISTORY {
+            self.undo_stack.remove(0);
+        }
+        self.redo_stack.clear();
+    }
+
     fn set_cell_color(&mut self, x: usize, y: usize, color: Color) {
         if y < self.grid.len() && x < self.grid[y].len() {
+            self.push_undo();
             self.grid[y][x] = color;
         }
     }
 
-    fn render(&self, area: Rect, f: &mut Frame) {
-        let cell_width = area.width / self.grid[0].len() as u16;
-        let cell_height = area.height / self.grid.len() as u16;
+    fn selection_rect(
+        anchor: (usize, usize),
+        cursor: (usize, usize),
+    ) -> ((usize, usize), (usize, usize)) {
+        let min_x = anchor.0.min(cursor.0);
+        let max_x = anchor.0.max(cursor.0);
+        let min_y = anchor.1.min(cursor.1);
+        let max_y = anchor.1.max(cursor.1);
+        ((min_x, min_y), (max_x, max_y))
+    }
 
-        for (y, row) in self.grid.iter().enumerate() {
-            for (x, &color) in row.iter().enumerate() {
-                let x_pos = area.x + x as u16 * cell_width;
-                let y_pos = area.y + y as u16 * cell_height;
+    // The rectangle currently shown to the user: the in-progress drag while an
+    // anchor is set, otherwise the last finalized selection.
+    fn current_selection(&self) -> Option<((usize, usize), (usize, usize))> {
+        match self.selection_anchor {
+            Some(anchor) => Some(Self::selection_rect(anchor, self.cursor)),
+            None => self.selection,
+        }
+    }
+
+    fn toggle_selection(&mut self) {
+        match self.selection_anchor {
+            None => {
+                self.selection_anchor = Some(self.cursor);
+                self.selection = None;
+            }
+            Some(anchor) => {
+                self.selection = Some(Self::selection_rect(anchor, self.cursor));
+                self.selection_anchor = None;
+            }
+        }
+    }
+
+    // `self.selection`'s corners are always in-bounds: `clamp_to_grid` drops
+    // the selection entirely rather than leaving a stale, partially
+    // out-of-bounds rectangle behind.
+    fn fill_selection(&mut self, color: Color) {
+        if let Some(((min_x, min_y), (max_x, max_y))) = self.selection {
+            self.push_undo();
+            for y in min_y..=max_y {
+                for x in min_x..=max_x {
+                    self.grid[y][x] = color;
+                }
+            }
+        }
+    }
+
+    fn clear_selection(&mut self) {
+        self.fill_selection(Color::Reset);
+    }
+
+    fn undo(&mut self) {
+        if let Some(prev) = self.undo_stack.pop() {
+            self.redo_stack.push(std::mem::replace(&mut self.grid, prev));
+            self.clamp_to_grid();
+        }
+    }
+
+    fn redo(&mut self) {
+        if let Some(next) = self.redo_stack.pop() {
+            self.undo_stack.push(std::mem::replace(&mut self.grid, next));
+            self.clamp_to_grid();
+        }
+    }
+}
+
+struct DrawingWidget;
+
+impl StatefulWidget for DrawingWidget {
+    type State = DrawingState;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut DrawingState) {
+        let canvas_width = state.grid[0].len();
+        let canvas_height = state.grid.len();
+        let visible_cols = ((area.width / CELL_PIXEL_WIDTH).max(1) as usize).min(canvas_width);
+        let visible_rows = ((area.height / CELL_PIXEL_HEIGHT).max(1) as usize).min(canvas_height);
+
+        state.update_scroll(visible_cols, visible_rows);
+        let (off_x, off_y) = state.scroll;
+
+        for y in 0..visible_rows {
+            for x in 0..visible_cols {
+                let color = state.grid[off_y + y][off_x + x];
+                let x_pos = area.x + x as u16 * CELL_PIXEL_WIDTH;
+                let y_pos = area.y + y as u16 * CELL_PIXEL_HEIGHT;
                 let cell = Paragraph::new("░").style(Style::default().bg(color));
-                f.render_widget(cell, Rect::new(x_pos, y_pos, cell_width, cell_height));
+                cell.render(
+                    Rect::new(x_pos, y_pos, CELL_PIXEL_WIDTH, CELL_PIXEL_HEIGHT),
+                    buf,
+                );
             }
         }
 
-        // Highlight the cursor
-        let (cursor_x, cursor_y) = self.cursor;
-        let cursor_x_pos = area.x + cursor_x as u16 * cell_width;
-        let cursor_y_pos = area.y + cursor_y as u16 * cell_height;
+        // Highlight the selection rectangle, if any, with a reversed-video
+        // overlay, clipped to the visible window.
+        if let Some(((min_x, min_y), (max_x, max_y))) = state.current_selection() {
+            let vis_min_x = min_x.max(off_x);
+            let vis_max_x = max_x.min(off_x + visible_cols - 1);
+            let vis_min_y = min_y.max(off_y);
+            let vis_max_y = max_y.min(off_y + visible_rows - 1);
+            for y in vis_min_y..=vis_max_y {
+                for x in vis_min_x..=vis_max_x {
+                    let x_pos = area.x + (x - off_x) as u16 * CELL_PIXEL_WIDTH;
+                    let y_pos = area.y + (y - off_y) as u16 * CELL_PIXEL_HEIGHT;
+                    let overlay = Paragraph::new("░")
+                        .style(Style::default().add_modifier(Modifier::REVERSED));
+                    overlay.render(
+                        Rect::new(x_pos, y_pos, CELL_PIXEL_WIDTH, CELL_PIXEL_HEIGHT),
+                        buf,
+                    );
+                }
+            }
+        }
+
+        // Highlight the cursor, which is always inside the viewport thanks
+        // to `update_scroll`.
+        let (cursor_x, cursor_y) = state.cursor;
+        let cursor_x_pos = area.x + (cursor_x - off_x) as u16 * CELL_PIXEL_WIDTH;
+        let cursor_y_pos = area.y + (cursor_y - off_y) as u16 * CELL_PIXEL_HEIGHT;
         let cursor_cell = Paragraph::new("█").style(Style::default().bg(Color::DarkGray));
-        f.render_widget(
-            cursor_cell,
-            Rect::new(cursor_x_pos, cursor_y_pos, cell_width, cell_height),
+        cursor_cell.render(
+            Rect::new(cursor_x_pos, cursor_y_pos, CELL_PIXEL_WIDTH, CELL_PIXEL_HEIGHT),
+            buf,
         );
     }
 }
@@ -79,6 +297,7 @@ enum Tool {
     Pencil,
     Eraser,
     ColorPicker,
+    Select,
 }
 
 impl ToolsWidget {
@@ -93,9 +312,10 @@ impl ToolsWidget {
             .direction(Direction::Horizontal)
             .constraints(
                 [
-                    Constraint::Percentage(33),
-                    Constraint::Percentage(33),
-                    Constraint::Percentage(33),
+                    Constraint::Percentage(25),
+                    Constraint::Percentage(25),
+                    Constraint::Percentage(25),
+                    Constraint::Percentage(25),
                 ]
                 .as_ref(),
             )
@@ -125,18 +345,32 @@ impl ToolsWidget {
             },
         );
         f.render_widget(color_picker_button, chunks[2]);
+
+        let select_button =
+            Paragraph::new("4 Select").style(if matches!(self.selected_tool, Tool::Select) {
+                Style::default().fg(Color::Yellow)
+            } else {
+                Style::default()
+            });
+        f.render_widget(select_button, chunks[3]);
     }
 }
 
-struct ColorPickerOverlay {
-    colors: Vec<Color>,
-    selected_color: usize,
+// A named palette the color picker grid is populated from. `Custom` carries
+// its own swatches, parsed from a comma-separated list of hex colors.
+enum ColorScheme {
+    Default,
+    Gruvbox,
+    GruvboxLight,
+    Nord,
+    NordLight,
+    Custom(Vec<Color>),
 }
 
-impl ColorPickerOverlay {
-    fn new() -> ColorPickerOverlay {
-        ColorPickerOverlay {
-            colors: vec![
+impl ColorScheme {
+    fn colors(&self) -> Vec<Color> {
+        match self {
+            ColorScheme::Default => vec![
                 Color::Black,
                 Color::Red,
                 Color::Green,
@@ -146,11 +380,135 @@ impl ColorPickerOverlay {
                 Color::Cyan,
                 Color::White,
             ],
+            ColorScheme::Gruvbox => vec![
+                Color::Rgb(0x28, 0x28, 0x28),
+                Color::Rgb(0xcc, 0x24, 0x1d),
+                Color::Rgb(0x98, 0x97, 0x1a),
+                Color::Rgb(0xd7, 0x99, 0x21),
+                Color::Rgb(0x45, 0x85, 0x88),
+                Color::Rgb(0xb1, 0x62, 0x86),
+                Color::Rgb(0x68, 0x9d, 0x6a),
+                Color::Rgb(0xeb, 0xdb, 0xb2),
+            ],
+            ColorScheme::GruvboxLight => vec![
+                Color::Rgb(0xfb, 0xf1, 0xc7),
+                Color::Rgb(0x9d, 0x00, 0x06),
+                Color::Rgb(0x79, 0x74, 0x0e),
+                Color::Rgb(0xb5, 0x76, 0x14),
+                Color::Rgb(0x07, 0x66, 0x78),
+                Color::Rgb(0x8f, 0x3f, 0x71),
+                Color::Rgb(0x42, 0x7b, 0x58),
+                Color::Rgb(0x3c, 0x38, 0x36),
+            ],
+            ColorScheme::Nord => vec![
+                Color::Rgb(0x2e, 0x34, 0x40),
+                Color::Rgb(0xbf, 0x61, 0x6a),
+                Color::Rgb(0xa3, 0xbe, 0x8c),
+                Color::Rgb(0xeb, 0xcb, 0x8b),
+                Color::Rgb(0x81, 0xa1, 0xc1),
+                Color::Rgb(0xb4, 0x8e, 0xad),
+                Color::Rgb(0x88, 0xc0, 0xd0),
+                Color::Rgb(0xe5, 0xe9, 0xf0),
+            ],
+            ColorScheme::NordLight => vec![
+                Color::Rgb(0xec, 0xef, 0xf4),
+                Color::Rgb(0xbf, 0x61, 0x6a),
+                Color::Rgb(0xa3, 0xbe, 0x8c),
+                Color::Rgb(0xd0, 0x87, 0x70),
+                Color::Rgb(0x5e, 0x81, 0xac),
+                Color::Rgb(0xb4, 0x8e, 0xad),
+                Color::Rgb(0x8f, 0xbc, 0xbb),
+                Color::Rgb(0x2e, 0x34, 0x40),
+            ],
+            ColorScheme::Custom(colors) => colors.clone(),
+        }
+    }
+}
+
+impl FromStr for ColorScheme {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "default" => Ok(ColorScheme::Default),
+            "gruvbox" => Ok(ColorScheme::Gruvbox),
+            "gruvbox-light" | "gruvboxlight" => Ok(ColorScheme::GruvboxLight),
+            "nord" => Ok(ColorScheme::Nord),
+            "nord-light" | "nordlight" => Ok(ColorScheme::NordLight),
+            _ => s
+                .split(',')
+                .map(parse_hex_color)
+                .collect::<Result<Vec<Color>, String>>()
+                .map(ColorScheme::Custom),
+        }
+    }
+}
+
+// Parses a `#rrggbb` (or `rrggbb`) hex string into a `Color::Rgb`.
+fn parse_hex_color(hex: &str) -> Result<Color, String> {
+    let hex = hex.trim().trim_start_matches('#');
+    if hex.len() != 6 || !hex.is_ascii() {
+        return Err(format!("invalid hex color: {hex}"));
+    }
+    let r = u8::from_str_radix(&hex[0..2], 16).map_err(|e| e.to_string())?;
+    let g = u8::from_str_radix(&hex[2..4], 16).map_err(|e| e.to_string())?;
+    let b = u8::from_str_radix(&hex[4..6], 16).map_err(|e| e.to_string())?;
+    Ok(Color::Rgb(r, g, b))
+}
+
+// Which half of the overlay is currently driving the preview color.
+enum PickerMode {
+    Swatches,
+    Rgb,
+}
+
+struct ColorPickerOverlay {
+    colors: Vec<Color>,
+    selected_color: usize,
+    mode: PickerMode,
+    rgb: [u8; 3],
+    focused_channel: usize,
+    hex_input: String,
+}
+
+impl ColorPickerOverlay {
+    fn new(colors: Vec<Color>) -> ColorPickerOverlay {
+        ColorPickerOverlay {
+            colors,
             selected_color: 0,
+            mode: PickerMode::Swatches,
+            rgb: [0, 0, 0],
+            focused_channel: 0,
+            hex_input: String::new(),
+        }
+    }
+
+    // The color staged for selection in whichever mode is active; this is
+    // what `Enter` commits back into `App::selected_color`.
+    fn preview_color(&self) -> Color {
+        match self.mode {
+            PickerMode::Swatches => self.colors[self.selected_color],
+            PickerMode::Rgb => Color::Rgb(self.rgb[0], self.rgb[1], self.rgb[2]),
         }
     }
 
     fn handle_input(&mut self, key: KeyCode) {
+        if key == KeyCode::Tab {
+            self.mode = match self.mode {
+                PickerMode::Swatches => PickerMode::Rgb,
+                PickerMode::Rgb => PickerMode::Swatches,
+            };
+            self.hex_input.clear();
+            return;
+        }
+
+        match self.mode {
+            PickerMode::Swatches => self.handle_swatch_input(key),
+            PickerMode::Rgb => self.handle_rgb_input(key),
+        }
+    }
+
+    fn handle_swatch_input(&mut self, key: KeyCode) {
         match key {
             KeyCode::Up => {
                 if self.selected_color >= 4 {
@@ -180,21 +538,73 @@ impl ColorPickerOverlay {
         }
     }
 
+    fn handle_rgb_input(&mut self, key: KeyCode) {
+        match key {
+            KeyCode::Up => {
+                self.rgb[self.focused_channel] = self.rgb[self.focused_channel].saturating_add(1);
+            }
+            KeyCode::Down => {
+                self.rgb[self.focused_channel] = self.rgb[self.focused_channel].saturating_sub(1);
+            }
+            KeyCode::Left if self.focused_channel > 0 => self.focused_channel -= 1,
+            KeyCode::Right if self.focused_channel + 1 < self.rgb.len() => {
+                self.focused_channel += 1;
+            }
+            KeyCode::Char(c) if c == '#' || c.is_ascii_hexdigit() => {
+                self.hex_input.push(c);
+                self.apply_hex_input_if_complete();
+            }
+            KeyCode::Backspace => {
+                self.hex_input.pop();
+            }
+            _ => {}
+        }
+    }
+
+    // Parses `hex_input` once it holds a full `#rrggbb` (or `rrggbb`) and
+    // feeds the result into the R/G/B channels, mirroring how typed hex
+    // colors are parsed for a `ColorScheme::Custom`.
+    fn apply_hex_input_if_complete(&mut self) {
+        if let Ok(Color::Rgb(r, g, b)) = parse_hex_color(&self.hex_input) {
+            self.rgb = [r, g, b];
+            self.hex_input.clear();
+        }
+    }
+
     fn render(&self, area: Rect, f: &mut Frame) {
         let chunks = Layout::default()
             .direction(Direction::Vertical)
             .constraints([Constraint::Percentage(80), Constraint::Percentage(20)].as_ref())
             .split(area);
 
+        match self.mode {
+            PickerMode::Swatches => self.render_swatches(chunks[0], f),
+            PickerMode::Rgb => self.render_rgb_editor(chunks[0], f),
+        }
+
+        // Render selected color preview
+        let label = match self.mode {
+            PickerMode::Swatches => "Selected Color".to_string(),
+            PickerMode::Rgb if self.hex_input.is_empty() => {
+                "Selected Color (type # for hex entry)".to_string()
+            }
+            PickerMode::Rgb => format!("Selected Color (hex: {})", self.hex_input),
+        };
+        let selected_color_preview =
+            Paragraph::new(label).style(Style::default().bg(self.preview_color()));
+        f.render_widget(selected_color_preview, chunks[1]);
+    }
+
+    fn render_swatches(&self, area: Rect, f: &mut Frame) {
         // Render color grid
         let num_colors = self.colors.len();
         let num_columns = 4; // Number of columns in the grid
-        let num_rows = (num_colors + num_columns - 1) / num_columns; // Calculate number of rows
+        let num_rows = num_colors.div_ceil(num_columns); // Calculate number of rows
 
         let color_grid = Layout::default()
             .direction(Direction::Vertical)
             .constraints(vec![Constraint::Length(1); num_rows])
-            .split(chunks[0]);
+            .split(area);
 
         for (i, &color) in self.colors.iter().enumerate() {
             let row = i / num_columns;
@@ -208,59 +618,95 @@ impl ColorPickerOverlay {
             let cell = Paragraph::new(" ").style(Style::default().bg(color));
             f.render_widget(cell, Rect::new(x, y, cell_width, cell_height));
         }
+    }
 
-        // Render selected color preview
-        let selected_color_preview = Paragraph::new("Selected Color")
-            .style(Style::default().bg(self.colors[self.selected_color]));
-        f.render_widget(selected_color_preview, chunks[1]);
+    fn render_rgb_editor(&self, area: Rect, f: &mut Frame) {
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(1); 3])
+            .split(area);
+
+        let labels = ["R", "G", "B"];
+        for (i, &row) in rows.iter().enumerate() {
+            let style = if i == self.focused_channel {
+                Style::default().fg(Color::Yellow)
+            } else {
+                Style::default()
+            };
+            let text = Paragraph::new(format!("{}: {:3}", labels[i], self.rgb[i])).style(style);
+            f.render_widget(text, row);
+        }
     }
 }
 
 struct App {
-    drawing_widget: DrawingWidget,
+    drawing_state: DrawingState,
     tools_widget: ToolsWidget,
     color_picker_overlay: Option<ColorPickerOverlay>,
     selected_tool: Tool,
     selected_color: Color,
+    palette: Vec<Color>,
 }
 
 impl App {
-    fn new() -> App {
+    fn new(scheme: ColorScheme) -> App {
         App {
-            drawing_widget: DrawingWidget::new(16, 16), // Example: 16x16 grid
+            drawing_state: DrawingState::new(64, 64), // Example: 64x64 grid, larger than most viewports
             tools_widget: ToolsWidget::new(),
             color_picker_overlay: None,
             selected_tool: Tool::Pencil,
             selected_color: Color::White,
+            palette: scheme.colors(),
         }
     }
 
-    fn handle_input(&mut self, key: KeyCode) {
+    fn handle_input(&mut self, key: KeyEvent) {
         if let Some(overlay) = &mut self.color_picker_overlay {
-            overlay.handle_input(key);
-            if let KeyCode::Enter = key {
-                self.selected_color = overlay.colors[overlay.selected_color];
+            overlay.handle_input(key.code);
+            if let KeyCode::Enter = key.code {
+                self.selected_color = overlay.preview_color();
                 self.color_picker_overlay = None; // Close the overlay
             }
+        } else if key.code == KeyCode::Char('r') && key.modifiers.contains(KeyModifiers::CONTROL) {
+            self.drawing_state.redo();
         } else {
-            match key {
+            match key.code {
                 KeyCode::Char('1') => self.selected_tool = Tool::Pencil,
                 KeyCode::Char('2') => self.selected_tool = Tool::Eraser,
                 KeyCode::Char('3') => {
-                    self.color_picker_overlay = Some(ColorPickerOverlay::new());
+                    self.color_picker_overlay = Some(ColorPickerOverlay::new(self.palette.clone()));
                 }
-                KeyCode::Up => self.drawing_widget.move_cursor(0, -1),
-                KeyCode::Down => self.drawing_widget.move_cursor(0, 1),
-                KeyCode::Left => self.drawing_widget.move_cursor(-1, 0),
-                KeyCode::Right => self.drawing_widget.move_cursor(1, 0),
+                KeyCode::Char('4') => self.selected_tool = Tool::Select,
+                KeyCode::Up => self.drawing_state.move_cursor(0, -1),
+                KeyCode::Down => self.drawing_state.move_cursor(0, 1),
+                KeyCode::Left => self.drawing_state.move_cursor(-1, 0),
+                KeyCode::Right => self.drawing_state.move_cursor(1, 0),
                 KeyCode::Char(' ') => {
-                    let (x, y) = self.drawing_widget.cursor;
+                    let (x, y) = self.drawing_state.cursor;
                     match self.selected_tool {
-                        Tool::Pencil => self.drawing_widget.set_cell_color(x, y, self.selected_color),
-                        Tool::Eraser => self.drawing_widget.set_cell_color(x, y, Color::Reset),
+                        Tool::Pencil => self.drawing_state.set_cell_color(x, y, self.selected_color),
+                        Tool::Eraser => self.drawing_state.set_cell_color(x, y, Color::Reset),
+                        Tool::Select => self.drawing_state.toggle_selection(),
                         _ => {}
                     }
                 }
+                KeyCode::Char('f') => {
+                    if matches!(self.selected_tool, Tool::Select) {
+                        self.drawing_state.fill_selection(self.selected_color);
+                    }
+                }
+                KeyCode::Delete => {
+                    if matches!(self.selected_tool, Tool::Select) {
+                        self.drawing_state.clear_selection();
+                    }
+                }
+                KeyCode::Char('u') => self.drawing_state.undo(),
+                KeyCode::Char('+') => self
+                    .drawing_state
+                    .resize_canvas(CANVAS_RESIZE_STEP as isize),
+                KeyCode::Char('-') => self
+                    .drawing_state
+                    .resize_canvas(-(CANVAS_RESIZE_STEP as isize)),
                 _ => {}
             }
         }
@@ -280,8 +726,14 @@ fn main() -> io::Result<()> {
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
-    // Create application
-    let mut app = App::new();
+    // Create application, picking the palette from a `--scheme <name>` flag
+    // (e.g. `--scheme gruvbox`, or a comma-separated hex list for a custom one).
+    let scheme = std::env::args()
+        .skip_while(|arg| arg != "--scheme")
+        .nth(1)
+        .and_then(|name| ColorScheme::from_str(&name).ok())
+        .unwrap_or(ColorScheme::Default);
+    let mut app = App::new(scheme);
 
     // Main loop
     loop {
@@ -291,7 +743,7 @@ fn main() -> io::Result<()> {
                 .constraints([Constraint::Percentage(90), Constraint::Percentage(10)].as_ref())
                 .split(f.area());
 
-            app.drawing_widget.render(chunks[0], f);
+            f.render_stateful_widget(DrawingWidget, chunks[0], &mut app.drawing_state);
             app.tools_widget.render(chunks[1], f);
 
             if let Some(overlay) = &app.color_picker_overlay {
@@ -303,7 +755,7 @@ fn main() -> io::Result<()> {
         if let Event::Key(key) = event::read()? {
             match key.code {
                 KeyCode::Char('q') => break,
-                _ => app.handle_input(key.code),
+                _ => app.handle_input(key),
             }
         }
     }